@@ -0,0 +1,51 @@
+//! Common HAL for the nRF52 family of microcontrollers.
+
+#![no_std]
+
+#[cfg(feature = "9160")]
+pub use nrf9160_pac as target;
+
+#[cfg(feature = "52832")]
+pub use nrf52832_pac as target;
+
+#[cfg(feature = "52840")]
+pub use nrf52840_pac as target;
+
+pub mod gpio;
+pub mod twim;
+pub mod twis;
+
+pub use twim::Twim;
+pub use twis::Twis;
+
+pub mod target_constants {
+    // NRF52840 and NRF9160 have a wider EasyDMA MAXCNT than the NRF52832.
+    #[cfg(feature = "52832")]
+    pub const EASY_DMA_SIZE: usize = 255;
+    #[cfg(feature = "52840")]
+    pub const EASY_DMA_SIZE: usize = 65535;
+    #[cfg(feature = "9160")]
+    pub const EASY_DMA_SIZE: usize = 65535;
+
+    // Bounds of the data RAM region, used to keep EasyDMA buffers off flash.
+    pub const SRAM_LOWER: usize = 0x2000_0000;
+    pub const SRAM_UPPER: usize = 0x3000_0000;
+}
+
+use target_constants::{SRAM_LOWER, SRAM_UPPER};
+
+/// Does this slice reside entirely within RAM?
+pub(crate) fn slice_in_ram(slice: &[u8]) -> bool {
+    let ptr = slice.as_ptr() as usize;
+    ptr >= SRAM_LOWER && (ptr + slice.len()) < SRAM_UPPER
+}
+
+/// Return `err` if the given slice does not reside entirely within RAM, so an
+/// EasyDMA peripheral is never pointed at flash.
+pub fn slice_in_ram_or<T>(slice: &[u8], err: T) -> Result<(), T> {
+    if slice.is_empty() || slice_in_ram(slice) {
+        Ok(())
+    } else {
+        Err(err)
+    }
+}
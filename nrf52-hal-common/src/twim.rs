@@ -0,0 +1,966 @@
+//! HAL interface to the TWIM peripheral
+//!
+//! See product specification:
+//!
+//! - nRF52832: Section 33
+//! - nRF52840: Section 6.31
+
+use core::future::poll_fn;
+use core::ops::Deref;
+use core::sync::atomic::{compiler_fence, Ordering::SeqCst};
+use core::task::Poll;
+
+use embedded_hal::i2c::{self, ErrorKind, ErrorType, I2c, SevenBitAddress};
+use futures::task::AtomicWaker;
+
+use crate::target::Interrupt;
+
+#[cfg(feature = "9160")]
+use crate::target::{twim0_ns as twim0, TWIM0_NS as TWIM0};
+
+#[cfg(not(feature = "9160"))]
+use crate::target::{twim0, TWIM0};
+
+#[cfg(any(feature = "52832", feature = "52840"))]
+use crate::target::TWIM1;
+
+use crate::target::P0;
+#[cfg(any(feature = "52840", feature = "9160"))]
+use crate::target::P1;
+
+use crate::{
+    gpio::{Floating, Input, Pin, Port},
+    slice_in_ram, slice_in_ram_or,
+    target_constants::EASY_DMA_SIZE,
+};
+
+/// Size of the internal RAM scratch buffer used to stage flash-resident TX
+/// data, since EasyDMA can only read from RAM.
+const FORCE_COPY_BUFFER_SIZE: usize = 512;
+
+/// Interface to a TWIM instance.
+///
+/// This is a very basic interface that comes with the following limitations:
+/// - The TWIM instances share the same address space with instances of SPIM,
+///   SPIS, SPI, TWIS, and TWI. For example, TWIM0 conflicts with SPIM0, SPIS0,
+///   etc.; you can only use one of them at a time.
+pub struct Twim<T>(T);
+
+impl<T> Twim<T>
+where
+    T: Instance,
+{
+    pub fn new(twim: T, sda: Pin<Input<Floating>>, scl: Pin<Input<Floating>>, config: Config) -> Self {
+        // The TWIM peripheral requires the pins to be configured as inputs
+        // with the `S0D1` drive strength, which is not expressible through the
+        // GPIO HAL, so we program the pin configuration registers directly.
+        for &(pin, pullup) in &[(&sda, config.sda_pullup), (&scl, config.scl_pullup)] {
+            // Program the configuration into the GPIO block that actually owns
+            // the pad, since `psel_bits()` routes TWIM by port as well as pin.
+            let gpio = unsafe {
+                match pin.port() {
+                    Port::Port0 => &*P0::ptr(),
+                    #[cfg(any(feature = "52840", feature = "9160"))]
+                    Port::Port1 => &*P1::ptr(),
+                }
+            };
+            gpio.pin_cnf[pin.pin() as usize].write(|w| {
+                w.dir().input();
+                w.input().connect();
+                if pullup {
+                    w.pull().pullup();
+                } else {
+                    w.pull().disabled();
+                }
+                w.drive().s0d1();
+                w.sense().disabled();
+                w
+            });
+        }
+
+        // Select pins.
+        twim.psel.sda.write(|w| {
+            unsafe { w.bits(sda.psel_bits()) };
+            w.connect().connected()
+        });
+        twim.psel.scl.write(|w| {
+            unsafe { w.bits(scl.psel_bits()) };
+            w.connect().connected()
+        });
+
+        // Enable TWIM instance.
+        twim.enable.write(|w| w.enable().enabled());
+
+        // Configure frequency.
+        twim.frequency
+            .write(|w| unsafe { w.frequency().bits(config.frequency as u32) });
+
+        Twim(twim)
+    }
+
+    /// Write to an I2C slave
+    ///
+    /// Buffers longer than `EASY_DMA_SIZE` are written transparently in
+    /// `EASY_DMA_SIZE`-sized spans, so there is no length limit on `buffer`.
+    /// The spans are joined into a single bus transaction using the same
+    /// suspend/deferred-resume dance as [`Twim::transaction`] (the
+    /// `lasttx_suspend` short holds the bus after each non-final span and the
+    /// next span issues the deferred RESUME), so only one STOP is emitted.
+    ///
+    /// If `buffer` is not located in RAM, its contents are transparently
+    /// staged through an internal RAM scratch buffer of `FORCE_COPY_BUFFER_SIZE`
+    /// bytes; the staged spans are stitched into the same single transaction,
+    /// so that flash-resident constants can be written without manual staging.
+    pub fn write(&mut self, address: u8, buffer: &[u8]) -> Result<(), Error> {
+        compiler_fence(SeqCst);
+
+        self.0
+            .address
+            .write(|w| unsafe { w.address().bits(address) });
+        self.0.shorts.reset();
+
+        let result = if buffer.is_empty() || slice_in_ram(buffer) {
+            self.write_spans(buffer, true, true)
+        } else {
+            let mut scratch = [0u8; FORCE_COPY_BUFFER_SIZE];
+            let mut chunks = buffer.chunks(FORCE_COPY_BUFFER_SIZE).peekable();
+            let mut first = true;
+            let mut result = Ok(());
+
+            while let Some(chunk) = chunks.next() {
+                scratch[..chunk.len()].copy_from_slice(chunk);
+                let last = chunks.peek().is_none();
+                result = self.write_spans(&scratch[..chunk.len()], first, last);
+                if result.is_err() {
+                    break;
+                }
+                first = false;
+            }
+
+            result
+        };
+
+        self.0.shorts.reset();
+        compiler_fence(SeqCst);
+
+        result
+    }
+
+    /// Write `buffer` as one or more `EASY_DMA_SIZE` spans of a running
+    /// transaction. `first` marks the leading span as the one that must *not*
+    /// issue a deferred RESUME, and `last` marks the trailing span as the one
+    /// that terminates the transaction with a STOP.
+    fn write_spans(&mut self, buffer: &[u8], first: bool, last: bool) -> Result<(), Error> {
+        // An empty buffer still drives one zero-length span so that an
+        // address-only write reaches the bus.
+        if buffer.is_empty() {
+            return self.write_span(buffer, first, last);
+        }
+
+        let mut spans = buffer.chunks(EASY_DMA_SIZE).peekable();
+        let mut first = first;
+        while let Some(span) = spans.next() {
+            let is_last = last && spans.peek().is_none();
+            self.write_span(span, first, is_last)?;
+            first = false;
+        }
+
+        Ok(())
+    }
+
+    /// Write a single `EASY_DMA_SIZE`-bounded span, holding the bus for the
+    /// next span unless `is_last` is set. See [`Twim::run_op`] for the
+    /// suspend/deferred-resume rationale.
+    fn write_span(&mut self, buffer: &[u8], is_first: bool, is_last: bool) -> Result<(), Error> {
+        if buffer.len() > EASY_DMA_SIZE {
+            return Err(Error::TxBufferTooLong);
+        }
+
+        self.0
+            .txd
+            .ptr
+            .write(|w| unsafe { w.ptr().bits(buffer.as_ptr() as u32) });
+        self.0
+            .txd
+            .maxcnt
+            .write(|w| unsafe { w.maxcnt().bits(buffer.len() as _) });
+
+        self.0.shorts.write(|w| {
+            if is_last {
+                w.lasttx_stop().enabled()
+            } else {
+                // Hold the bus after the last byte; the next span resumes.
+                w.lasttx_suspend().enabled()
+            }
+        });
+
+        self.0.tasks_starttx.write(|w| unsafe { w.bits(1) });
+
+        // Release the clock stretch left by the previous span, now that this
+        // transfer is queued behind it.
+        if !is_first {
+            self.0.tasks_resume.write(|w| unsafe { w.bits(1) });
+        }
+
+        if is_last {
+            while self.0.events_stopped.read().bits() == 0 {}
+            self.0.events_stopped.write(|w| w);
+        } else {
+            while self.0.events_lasttx.read().bits() == 0 {}
+            self.0.events_lasttx.write(|w| w);
+        }
+
+        if self.0.txd.amount.read().bits() != buffer.len() as u32 {
+            return Err(Error::Transmit);
+        }
+
+        Ok(())
+    }
+
+    /// Read from an I2C slave
+    ///
+    /// Buffers longer than `EASY_DMA_SIZE` are read transparently in
+    /// `EASY_DMA_SIZE`-sized spans joined into a single bus transaction: each
+    /// non-final span leaves the peripheral SUSPENDed (hand-issued, since the
+    /// nRF52832 has no `LASTRX_SUSPEND` short) and the next span issues the
+    /// deferred RESUME, so only one STOP is emitted and there is no length
+    /// limit on `buffer`.
+    pub fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Error> {
+        // NOTE: RAM slice check is not necessary, as a mutable slice can only be
+        // built from data located in RAM
+
+        compiler_fence(SeqCst);
+
+        self.0
+            .address
+            .write(|w| unsafe { w.address().bits(address) });
+        self.0.shorts.reset();
+
+        let mut result = Ok(());
+        if buffer.is_empty() {
+            // An empty buffer still drives one zero-length span.
+            result = self.read_span(buffer, true, true);
+        } else {
+            let mut spans = buffer.chunks_mut(EASY_DMA_SIZE).peekable();
+            let mut first = true;
+            while let Some(span) = spans.next() {
+                let is_last = spans.peek().is_none();
+                if let Err(e) = self.read_span(span, first, is_last) {
+                    result = Err(e);
+                    break;
+                }
+                first = false;
+            }
+        }
+
+        self.0.shorts.reset();
+        compiler_fence(SeqCst);
+
+        result
+    }
+
+    /// Read a single `EASY_DMA_SIZE`-bounded span, holding the bus for the
+    /// next span unless `is_last` is set. See [`Twim::run_op`] for the
+    /// suspend/deferred-resume rationale.
+    fn read_span(&mut self, buffer: &mut [u8], is_first: bool, is_last: bool) -> Result<(), Error> {
+        if buffer.len() > EASY_DMA_SIZE {
+            return Err(Error::RxBufferTooLong);
+        }
+
+        self.0
+            .rxd
+            .ptr
+            .write(|w| unsafe { w.ptr().bits(buffer.as_mut_ptr() as u32) });
+        self.0
+            .rxd
+            .maxcnt
+            .write(|w| unsafe { w.maxcnt().bits(buffer.len() as _) });
+
+        if is_last {
+            self.0.shorts.write(|w| w.lastrx_stop().enabled());
+        } else {
+            // No LASTRX_SUSPEND short exists on the nRF52832, so the suspend is
+            // issued by hand after the last-RX event below.
+            self.0.shorts.reset();
+        }
+
+        self.0.tasks_startrx.write(|w| unsafe { w.bits(1) });
+
+        // Release the clock stretch left by the previous span, now that this
+        // transfer is queued behind it.
+        if !is_first {
+            self.0.tasks_resume.write(|w| unsafe { w.bits(1) });
+        }
+
+        if is_last {
+            while self.0.events_stopped.read().bits() == 0 {}
+            self.0.events_stopped.write(|w| w);
+        } else {
+            while self.0.events_lastrx.read().bits() == 0 {}
+            self.0.events_lastrx.write(|w| w);
+            // Hold the bus after the last byte; the next span resumes.
+            self.0.tasks_suspend.write(|w| unsafe { w.bits(1) });
+        }
+
+        if self.0.rxd.amount.read().bits() != buffer.len() as u32 {
+            return Err(Error::Receive);
+        }
+
+        Ok(())
+    }
+
+    /// Write data to an I2C slave, then read data from the slave without
+    /// triggering a stop condition between the two
+    ///
+    /// Each buffer must have a length of at most `EASY_DMA_SIZE` bytes (255 on
+    /// the nRF52832, 65535 on the nRF52840).
+    pub fn write_then_read(
+        &mut self,
+        address: u8,
+        wr_buffer: &[u8],
+        rd_buffer: &mut [u8],
+    ) -> Result<(), Error> {
+        if slice_in_ram(wr_buffer) {
+            self.write_then_read_inner(address, wr_buffer, rd_buffer)
+        } else {
+            // The combined write-then-read cannot be chunked, so the staged
+            // TX must fit in a single scratch buffer.
+            if wr_buffer.len() > FORCE_COPY_BUFFER_SIZE {
+                return Err(Error::TxBufferTooLong);
+            }
+            let mut scratch = [0u8; FORCE_COPY_BUFFER_SIZE];
+            scratch[..wr_buffer.len()].copy_from_slice(wr_buffer);
+            self.write_then_read_inner(address, &scratch[..wr_buffer.len()], rd_buffer)
+        }
+    }
+
+    fn write_then_read_inner(
+        &mut self,
+        address: u8,
+        wr_buffer: &[u8],
+        rd_buffer: &mut [u8],
+    ) -> Result<(), Error> {
+        if wr_buffer.len() > EASY_DMA_SIZE {
+            return Err(Error::TxBufferTooLong);
+        }
+
+        if rd_buffer.len() > EASY_DMA_SIZE {
+            return Err(Error::RxBufferTooLong);
+        }
+
+        // Conservative compiler fence to prevent optimizations that do not
+        // take in to account actions by DMA. The fence has been placed here,
+        // before any DMA action has started
+        compiler_fence(SeqCst);
+
+        self.0
+            .address
+            .write(|w| unsafe { w.address().bits(address) });
+
+        // Set up the DMA write
+        self.0.txd.ptr.write(|w|
+            // We're giving the register a pointer to the stack. Since we're
+            // waiting for the I2C transaction to end before this stack pointer
+            // becomes invalid, there's nothing wrong here.
+            //
+            // The PTR field is a full 32 bits wide and accepts the full range
+            // of values.
+            unsafe { w.ptr().bits(wr_buffer.as_ptr() as u32) });
+        self.0.txd.maxcnt.write(|w|
+            // We're giving it the length of the buffer, so no danger of
+            // accessing invalid memory. We have verified that the length of the
+            // buffer is at most `EASY_DMA_SIZE`, so the cast to the type of
+            // maxcnt is also fine.
+            //
+            // The MAXCNT field accepts the full range of values up to
+            // `EASY_DMA_SIZE`.
+            unsafe { w.maxcnt().bits(wr_buffer.len() as _) });
+
+        // Set up the DMA read
+        self.0.rxd.ptr.write(|w|
+            // We're giving the register a pointer to the stack. Since we're
+            // waiting for the I2C transaction to end before this stack pointer
+            // becomes invalid, there's nothing wrong here.
+            //
+            // The PTR field is a full 32 bits wide and accepts the full range
+            // of values.
+            unsafe { w.ptr().bits(rd_buffer.as_mut_ptr() as u32) });
+        self.0.rxd.maxcnt.write(|w|
+            // We're giving it the length of the buffer, so no danger of
+            // accessing invalid memory. We have verified that the length of the
+            // buffer is at most `EASY_DMA_SIZE`, so the cast to the type of
+            // maxcnt is also fine.
+            //
+            // Note that that nrf52840 maxcnt is a wider
+            // type than a u8, so we use a `_` cast rather than a `u8` cast.
+            // The MAXCNT field accepts the full range of values up to
+            // `EASY_DMA_SIZE`.
+            unsafe { w.maxcnt().bits(rd_buffer.len() as _) });
+
+        // Immediately start RX after TX, then stop
+        self.0
+            .shorts
+            .modify(|_r, w| w.lasttx_startrx().enabled().lastrx_stop().enabled());
+
+        // Start write operation
+        self.0.tasks_starttx.write(|w|
+            // `1` is a valid value to write to task registers.
+            unsafe { w.bits(1) });
+
+        // Wait until total operation has ended
+        while self.0.events_stopped.read().bits() == 0 {}
+
+        self.0.events_lasttx.write(|w| w); // reset event
+        self.0.events_lastrx.write(|w| w); // reset event
+        self.0.events_stopped.write(|w| w); // reset event
+        self.0.shorts.write(|w| w);
+
+        // Conservative compiler fence to prevent optimizations that do not
+        // take in to account actions by DMA. The fence has been placed here,
+        // after all possible DMA actions have completed
+        compiler_fence(SeqCst);
+
+        let bad_write = self.0.txd.amount.read().bits() != wr_buffer.len() as u32;
+        let bad_read = self.0.rxd.amount.read().bits() != rd_buffer.len() as u32;
+
+        if bad_write {
+            return Err(Error::Transmit);
+        }
+
+        if bad_read {
+            return Err(Error::Receive);
+        }
+
+        Ok(())
+    }
+
+    /// Execute a sequence of read and write operations against a single
+    /// address as one bus transaction: a repeated start is emitted between
+    /// operations and a single STOP only after the last one.
+    ///
+    /// A non-final operation leaves the peripheral SUSPENDed after its last
+    /// byte (via the `lasttx_suspend` short for writes, or a hand-issued
+    /// suspend for reads, since the nRF52832 has no `LASTRX_SUSPEND` short) so
+    /// the bus is held without a spurious stop; the following operation issues
+    /// the deferred RESUME once its transfer is queued. The final operation
+    /// terminates with `lasttx_stop`/`lastrx_stop`.
+    pub fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation],
+    ) -> Result<(), Error> {
+        compiler_fence(SeqCst);
+
+        self.0
+            .address
+            .write(|w| unsafe { w.address().bits(address) });
+        self.0.shorts.reset();
+
+        let last = operations.len();
+        for i in 0..last {
+            self.run_op(&mut operations[i], i == 0, i == last - 1)?;
+        }
+
+        self.0.shorts.reset();
+
+        compiler_fence(SeqCst);
+
+        Ok(())
+    }
+
+    /// Execute a single operation of a transaction, assuming the address and
+    /// initial `shorts` state have already been programmed.
+    ///
+    /// A non-final operation leaves the peripheral SUSPENDed after its last
+    /// byte so the bus is held (clock stretched) across the inter-op gap while
+    /// the CPU programs the next span. The deferred RESUME is issued by the
+    /// *following* operation, once its own start is queued — resuming here
+    /// with nothing queued would drop the bus and emit a spurious STOP.
+    /// `is_first` therefore gates that resume, and `is_last` selects the
+    /// terminating STOP.
+    fn run_op(&mut self, op: &mut Operation, is_first: bool, is_last: bool) -> Result<(), Error> {
+        match op {
+            Operation::Write(buffer) => {
+                slice_in_ram_or(buffer, Error::DMABufferNotInDataMemory)?;
+                if buffer.len() > EASY_DMA_SIZE {
+                    return Err(Error::TxBufferTooLong);
+                }
+
+                self.0
+                    .txd
+                    .ptr
+                    .write(|w| unsafe { w.ptr().bits(buffer.as_ptr() as u32) });
+                self.0
+                    .txd
+                    .maxcnt
+                    .write(|w| unsafe { w.maxcnt().bits(buffer.len() as _) });
+
+                self.0.shorts.write(|w| {
+                    if is_last {
+                        w.lasttx_stop().enabled()
+                    } else {
+                        // Hold the bus after the last byte; the next op resumes.
+                        w.lasttx_suspend().enabled()
+                    }
+                });
+
+                self.0.tasks_starttx.write(|w| unsafe { w.bits(1) });
+
+                // Release the clock stretch left by the previous op, now that
+                // this transfer is queued behind it.
+                if !is_first {
+                    self.0.tasks_resume.write(|w| unsafe { w.bits(1) });
+                }
+
+                if is_last {
+                    while self.0.events_stopped.read().bits() == 0 {}
+                    self.0.events_stopped.write(|w| w);
+                } else {
+                    while self.0.events_lasttx.read().bits() == 0 {}
+                    self.0.events_lasttx.write(|w| w);
+                }
+
+                if self.0.txd.amount.read().bits() != buffer.len() as u32 {
+                    return Err(Error::Transmit);
+                }
+            }
+            Operation::Read(buffer) => {
+                if buffer.len() > EASY_DMA_SIZE {
+                    return Err(Error::RxBufferTooLong);
+                }
+
+                self.0
+                    .rxd
+                    .ptr
+                    .write(|w| unsafe { w.ptr().bits(buffer.as_mut_ptr() as u32) });
+                self.0
+                    .rxd
+                    .maxcnt
+                    .write(|w| unsafe { w.maxcnt().bits(buffer.len() as _) });
+
+                if is_last {
+                    self.0.shorts.write(|w| w.lastrx_stop().enabled());
+                } else {
+                    // No LASTRX_SUSPEND short exists on the nRF52832, so the
+                    // suspend is issued by hand after the last-RX event below.
+                    self.0.shorts.reset();
+                }
+
+                self.0.tasks_startrx.write(|w| unsafe { w.bits(1) });
+
+                // Release the clock stretch left by the previous op, now that
+                // this transfer is queued behind it.
+                if !is_first {
+                    self.0.tasks_resume.write(|w| unsafe { w.bits(1) });
+                }
+
+                if is_last {
+                    while self.0.events_stopped.read().bits() == 0 {}
+                    self.0.events_stopped.write(|w| w);
+                } else {
+                    while self.0.events_lastrx.read().bits() == 0 {}
+                    self.0.events_lastrx.write(|w| w);
+                    // Hold the bus after the last byte; the next op resumes.
+                    self.0.tasks_suspend.write(|w| unsafe { w.bits(1) });
+                }
+
+                if self.0.rxd.amount.read().bits() != buffer.len() as u32 {
+                    return Err(Error::Receive);
+                }
+            }
+        }
+
+        self.0.shorts.reset();
+
+        compiler_fence(SeqCst);
+
+        Ok(())
+    }
+
+    /// Write to an I2C slave, awaiting completion instead of busy-waiting.
+    ///
+    /// Relies on [`Twim::on_interrupt`] being wired up to this instance's
+    /// interrupt vector so the fired STOPPED/ERROR sources can wake the
+    /// parked task.
+    ///
+    /// Unlike the blocking [`Twim::write`], the async path does not split the
+    /// buffer into spans: it programs a single DMA transfer and returns
+    /// [`Error::TxBufferTooLong`] for buffers larger than `EASY_DMA_SIZE`.
+    pub async fn write_async(&mut self, address: u8, buffer: &[u8]) -> Result<(), Error> {
+        slice_in_ram_or(buffer, Error::DMABufferNotInDataMemory)?;
+
+        if buffer.len() > EASY_DMA_SIZE {
+            return Err(Error::TxBufferTooLong);
+        }
+
+        compiler_fence(SeqCst);
+
+        self.0
+            .address
+            .write(|w| unsafe { w.address().bits(address) });
+
+        self.0
+            .txd
+            .ptr
+            .write(|w| unsafe { w.ptr().bits(buffer.as_ptr() as u32) });
+        self.0
+            .txd
+            .maxcnt
+            .write(|w| unsafe { w.maxcnt().bits(buffer.len() as _) });
+
+        self.0
+            .shorts
+            .modify(|_r, w| w.lasttx_stop().enabled());
+
+        let result = self
+            .start_and_wait(|w| {
+                w.stopped().set_bit();
+                w.error().set_bit()
+            })
+            .await;
+
+        self.0.events_stopped.write(|w| w);
+        self.0.shorts.write(|w| w);
+
+        compiler_fence(SeqCst);
+
+        result.map_err(|()| Error::Transmit)?;
+
+        if self.0.txd.amount.read().bits() != buffer.len() as u32 {
+            return Err(Error::Transmit);
+        }
+
+        Ok(())
+    }
+
+    /// Read from an I2C slave, awaiting completion instead of busy-waiting.
+    ///
+    /// See [`Twim::write_async`] for the interrupt wiring requirement. Like
+    /// the other async methods it keeps the single-span limit and returns
+    /// [`Error::RxBufferTooLong`] for buffers larger than `EASY_DMA_SIZE`.
+    pub async fn read_async(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Error> {
+        if buffer.len() > EASY_DMA_SIZE {
+            return Err(Error::RxBufferTooLong);
+        }
+
+        compiler_fence(SeqCst);
+
+        self.0
+            .address
+            .write(|w| unsafe { w.address().bits(address) });
+
+        self.0
+            .rxd
+            .ptr
+            .write(|w| unsafe { w.ptr().bits(buffer.as_mut_ptr() as u32) });
+        self.0
+            .rxd
+            .maxcnt
+            .write(|w| unsafe { w.maxcnt().bits(buffer.len() as _) });
+
+        self.0
+            .shorts
+            .modify(|_r, w| w.lastrx_stop().enabled());
+
+        // The read path is started with STARTRX rather than STARTTX.
+        self.0.tasks_startrx.write(|w| unsafe { w.bits(1) });
+        let result = self
+            .wait(|w| {
+                w.stopped().set_bit();
+                w.error().set_bit()
+            })
+            .await;
+
+        self.0.events_stopped.write(|w| w);
+        self.0.shorts.write(|w| w);
+
+        compiler_fence(SeqCst);
+
+        result.map_err(|()| Error::Receive)?;
+
+        if self.0.rxd.amount.read().bits() != buffer.len() as u32 {
+            return Err(Error::Receive);
+        }
+
+        Ok(())
+    }
+
+    /// Write then read from an I2C slave without an intermediate stop,
+    /// awaiting completion instead of busy-waiting.
+    ///
+    /// See [`Twim::write_async`] for the interrupt wiring requirement.
+    pub async fn write_then_read_async(
+        &mut self,
+        address: u8,
+        wr_buffer: &[u8],
+        rd_buffer: &mut [u8],
+    ) -> Result<(), Error> {
+        slice_in_ram_or(wr_buffer, Error::DMABufferNotInDataMemory)?;
+
+        if wr_buffer.len() > EASY_DMA_SIZE {
+            return Err(Error::TxBufferTooLong);
+        }
+
+        if rd_buffer.len() > EASY_DMA_SIZE {
+            return Err(Error::RxBufferTooLong);
+        }
+
+        compiler_fence(SeqCst);
+
+        self.0
+            .address
+            .write(|w| unsafe { w.address().bits(address) });
+
+        self.0
+            .txd
+            .ptr
+            .write(|w| unsafe { w.ptr().bits(wr_buffer.as_ptr() as u32) });
+        self.0
+            .txd
+            .maxcnt
+            .write(|w| unsafe { w.maxcnt().bits(wr_buffer.len() as _) });
+        self.0
+            .rxd
+            .ptr
+            .write(|w| unsafe { w.ptr().bits(rd_buffer.as_mut_ptr() as u32) });
+        self.0
+            .rxd
+            .maxcnt
+            .write(|w| unsafe { w.maxcnt().bits(rd_buffer.len() as _) });
+
+        self.0
+            .shorts
+            .modify(|_r, w| w.lasttx_startrx().enabled().lastrx_stop().enabled());
+
+        let result = self
+            .start_and_wait(|w| {
+                w.stopped().set_bit();
+                w.error().set_bit()
+            })
+            .await;
+
+        self.0.events_stopped.write(|w| w);
+        self.0.shorts.write(|w| w);
+
+        compiler_fence(SeqCst);
+
+        // The error event cannot distinguish the TX from the RX phase; report
+        // it against the write that initiates the transaction.
+        result.map_err(|()| Error::Transmit)?;
+
+        let bad_write = self.0.txd.amount.read().bits() != wr_buffer.len() as u32;
+        let bad_read = self.0.rxd.amount.read().bits() != rd_buffer.len() as u32;
+
+        if bad_write {
+            return Err(Error::Transmit);
+        }
+
+        if bad_read {
+            return Err(Error::Receive);
+        }
+
+        Ok(())
+    }
+
+    /// Enable the given interrupt sources, kick off a TX, and park the task
+    /// until [`Twim::on_interrupt`] reports the transaction has settled.
+    ///
+    /// Returns `Err(())` if the ERROR event fired; the caller maps it to the
+    /// direction-appropriate [`Error`].
+    async fn start_and_wait<F>(&mut self, enable: F) -> Result<(), ()>
+    where
+        F: FnOnce(&mut crate::target::twim0::intenset::W) -> &mut crate::target::twim0::intenset::W,
+    {
+        self.0.tasks_starttx.write(|w| unsafe { w.bits(1) });
+        self.wait(enable).await
+    }
+
+    /// Enable the given interrupt sources and await the STOPPED event,
+    /// registering this instance's waker so the handler can resume us.
+    ///
+    /// Returns `Err(())` if the ERROR event fired instead. Without this the
+    /// future would park forever on a NACK: the handler clears all of
+    /// `intenclr` and wakes once, but STOPPED never arrives, so the re-poll
+    /// sees no progress and can never be woken again.
+    async fn wait<F>(&mut self, enable: F) -> Result<(), ()>
+    where
+        F: FnOnce(&mut crate::target::twim0::intenset::W) -> &mut crate::target::twim0::intenset::W,
+    {
+        self.0.intenset.write(|w| enable(w));
+
+        poll_fn(|cx| {
+            T::state().end_waker.register(cx.waker());
+
+            if self.0.events_error.read().bits() != 0 {
+                self.0.events_error.write(|w| w);
+                Poll::Ready(Err(()))
+            } else if self.0.events_stopped.read().bits() != 0 {
+                Poll::Ready(Ok(()))
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    /// Interrupt handler for this TWIM instance.
+    ///
+    /// Bind it to the instance's interrupt vector (the
+    /// `SPIMx_SPISx_TWIMx_TWISx_SPIx_TWIx` line). It disables every TWIM
+    /// interrupt source so the line cannot re-fire, then wakes the task that
+    /// is awaiting one of the async transfer methods.
+    pub fn on_interrupt() {
+        let twim = unsafe { &*T::REGISTERS };
+        twim.intenclr.write(|w| unsafe { w.bits(!0) });
+        T::state().end_waker.wake();
+    }
+
+    /// Return the raw interface to the underlying TWIM peripheral.
+    pub fn free(self) -> T {
+        self.0
+    }
+}
+
+/// Per-instance driver state shared with the interrupt handler.
+pub struct State {
+    end_waker: AtomicWaker,
+}
+
+impl State {
+    const fn new() -> Self {
+        State {
+            end_waker: AtomicWaker::new(),
+        }
+    }
+}
+
+/// TWIM bus frequency.
+///
+/// The values correspond to the peripheral's `FREQUENCY` register encoding.
+#[derive(Clone, Copy)]
+pub enum Frequency {
+    /// 100 kbps.
+    K100 = 26738688,
+    /// 250 kbps.
+    K250 = 67108864,
+    /// 400 kbps.
+    K400 = 104857600,
+}
+
+/// A single read or write within a [`Twim::transaction`].
+///
+/// This is `embedded_hal::i2c::Operation`, so the inherent `transaction` and
+/// the `embedded_hal` `I2c` impl share one engine.
+pub use embedded_hal::i2c::Operation;
+
+/// Configuration for a TWIM instance.
+pub struct Config {
+    /// Bus frequency.
+    pub frequency: Frequency,
+    /// Enable the internal pull-up on the SDA line.
+    pub sda_pullup: bool,
+    /// Enable the internal pull-up on the SCL line.
+    pub scl_pullup: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            frequency: Frequency::K100,
+            sda_pullup: false,
+            scl_pullup: false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    TxBufferTooLong,
+    RxBufferTooLong,
+    Transmit,
+    Receive,
+    DMABufferNotInDataMemory,
+}
+
+/// Implemented by all TWIM instances
+pub trait Instance: Deref<Target = twim0::RegisterBlock> {
+    /// Raw pointer to this instance's register block, for use from the
+    /// interrupt handler where no `&self` is available.
+    const REGISTERS: *const twim0::RegisterBlock;
+
+    /// Interrupt line shared by this instance.
+    const INTERRUPT: Interrupt;
+
+    /// Driver state shared between the futures and the interrupt handler.
+    fn state() -> &'static State;
+}
+
+static TWIM0_STATE: State = State::new();
+
+impl Instance for TWIM0 {
+    const REGISTERS: *const twim0::RegisterBlock = TWIM0::ptr();
+    const INTERRUPT: Interrupt = Interrupt::SPIM0_SPIS0_TWIM0_TWIS0_SPI0_TWI0;
+
+    fn state() -> &'static State {
+        &TWIM0_STATE
+    }
+}
+
+#[cfg(any(feature = "52832", feature = "52840"))]
+static TWIM1_STATE: State = State::new();
+
+#[cfg(any(feature = "52832", feature = "52840"))]
+impl Instance for TWIM1 {
+    const REGISTERS: *const twim0::RegisterBlock = TWIM1::ptr();
+    const INTERRUPT: Interrupt = Interrupt::SPIM1_SPIS1_TWIM1_TWIS1_SPI1_TWI1;
+
+    fn state() -> &'static State {
+        &TWIM1_STATE
+    }
+}
+
+impl i2c::Error for Error {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            // A NACK or short DMA transfer surfaces as a transmit/receive
+            // count mismatch; report it as a generic bus error.
+            Error::Transmit | Error::Receive => ErrorKind::Bus,
+            Error::TxBufferTooLong
+            | Error::RxBufferTooLong
+            | Error::DMABufferNotInDataMemory => ErrorKind::Other,
+        }
+    }
+}
+
+impl<T: Instance> ErrorType for Twim<T> {
+    type Error = Error;
+}
+
+impl<T: Instance> I2c<SevenBitAddress> for Twim<T> {
+    fn read(&mut self, address: u8, read: &mut [u8]) -> Result<(), Self::Error> {
+        self.read(address, read)
+    }
+
+    fn write(&mut self, address: u8, write: &[u8]) -> Result<(), Self::Error> {
+        self.write(address, write)
+    }
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        write: &[u8],
+        read: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.write_then_read(address, write, read)
+    }
+
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        // `Operation` is the embedded-hal type, so the inherent engine handles
+        // it directly.
+        Twim::transaction(self, address, operations)
+    }
+}
@@ -0,0 +1,411 @@
+//! HAL interface to the TWIS peripheral.
+//!
+//! The TWIS peripheral turns the nRF device into an I2C target (slave). It
+//! shares its address space with the TWIM/SPIM/SPIS/TWI instances of the same
+//! index (TWIS0 aliases TWIM0, and so on), so only one of them may be used at
+//! a time.
+//!
+//! See product specification:
+//!
+//! - nRF52832: Section 34
+//! - nRF52840: Section 6.32
+
+use core::future::poll_fn;
+use core::ops::Deref;
+use core::sync::atomic::{compiler_fence, Ordering::SeqCst};
+use core::task::Poll;
+
+use futures::task::AtomicWaker;
+
+#[cfg(feature = "9160")]
+use crate::target::{twis0_ns as twis0, TWIS0_NS as TWIS0};
+
+#[cfg(not(feature = "9160"))]
+use crate::target::{twis0, TWIS0};
+
+#[cfg(any(feature = "52832", feature = "52840"))]
+use crate::target::TWIS1;
+
+use crate::target::P0;
+#[cfg(any(feature = "52840", feature = "9160"))]
+use crate::target::P1;
+use crate::target::Interrupt;
+
+use crate::{
+    gpio::{Floating, Input, Pin, Port},
+    slice_in_ram_or,
+    target_constants::EASY_DMA_SIZE,
+};
+
+/// Interface to a TWIS instance.
+pub struct Twis<T>(T);
+
+impl<T> Twis<T>
+where
+    T: Instance,
+{
+    /// Create a TWIS target on the given pins, matching `address`.
+    pub fn new(twis: T, sda: Pin<Input<Floating>>, scl: Pin<Input<Floating>>, address: u8) -> Self {
+        // Configure the pins exactly as the TWIM master does: S0D1 inputs.
+        for pin in &[&sda, &scl] {
+            // Use the GPIO block that owns the pad; `psel_bits()` routes by
+            // port as well as pin.
+            let gpio = unsafe {
+                match pin.port() {
+                    Port::Port0 => &*P0::ptr(),
+                    #[cfg(any(feature = "52840", feature = "9160"))]
+                    Port::Port1 => &*P1::ptr(),
+                }
+            };
+            gpio.pin_cnf[pin.pin() as usize].write(|w| {
+                w.dir().input();
+                w.input().connect();
+                w.pull().disabled();
+                w.drive().s0d1();
+                w.sense().disabled();
+                w
+            });
+        }
+
+        twis.psel.sda.write(|w| {
+            unsafe { w.bits(sda.psel_bits()) };
+            w.connect().connected()
+        });
+        twis.psel.scl.write(|w| {
+            unsafe { w.bits(scl.psel_bits()) };
+            w.connect().connected()
+        });
+
+        // Match on the given 7-bit address via the first address slot.
+        twis.address[0].write(|w| unsafe { w.address().bits(address) });
+        twis.config.write(|w| w.address0().enabled());
+
+        twis.enable.write(|w| w.enable().enabled());
+
+        Twis(twis)
+    }
+
+    /// Enable matching on a second 7-bit address.
+    pub fn set_address1(&mut self, address: u8) {
+        self.0.address[1].write(|w| unsafe { w.address().bits(address) });
+        self.0.config.modify(|_r, w| w.address1().enabled());
+    }
+
+    /// Set the character clocked out when the master reads past the end of the
+    /// supplied TX buffer.
+    pub fn set_orc(&mut self, orc: u8) {
+        self.0.orc.write(|w| unsafe { w.orc().bits(orc) });
+    }
+
+    /// Block until the master addresses us, returning the direction of the
+    /// transfer it wants to perform and which configured address it matched.
+    ///
+    /// Returns [`Error::Receive`] if the ERROR event fires while we are waiting
+    /// to be addressed; the transfer direction is not yet known at that point,
+    /// so the error cannot be attributed to a specific direction.
+    pub fn wait(&mut self) -> Result<Command, Error> {
+        // READ/WRITE indicate the master's direction once it has addressed us;
+        // the MATCH register records which address slot it hit.
+        loop {
+            if self.0.events_read.read().bits() != 0 {
+                self.0.events_read.write(|w| w);
+                return Ok(Command::Read {
+                    address: self.matched_address(),
+                });
+            }
+            if self.0.events_write.read().bits() != 0 {
+                self.0.events_write.write(|w| w);
+                return Ok(Command::Write {
+                    address: self.matched_address(),
+                });
+            }
+            if self.0.events_error.read().bits() != 0 {
+                self.0.events_error.write(|w| w);
+                return Err(Error::Receive);
+            }
+        }
+    }
+
+    /// Async variant of [`Twis::wait`].
+    ///
+    /// Relies on [`Twis::on_interrupt`] being wired up so the READ/WRITE/ERROR
+    /// sources can wake the parked task.
+    pub async fn wait_command(&mut self) -> Result<Command, Error> {
+        self.0.intenset.write(|w| {
+            w.read().set_bit();
+            w.write().set_bit();
+            w.error().set_bit()
+        });
+
+        poll_fn(|cx| {
+            T::state().waker.register(cx.waker());
+
+            if self.0.events_error.read().bits() != 0 {
+                self.0.events_error.write(|w| w);
+                Poll::Ready(Err(Error::Receive))
+            } else if self.0.events_read.read().bits() != 0 {
+                self.0.events_read.write(|w| w);
+                Poll::Ready(Ok(Command::Read {
+                    address: self.matched_address(),
+                }))
+            } else if self.0.events_write.read().bits() != 0 {
+                self.0.events_write.write(|w| w);
+                Poll::Ready(Ok(Command::Write {
+                    address: self.matched_address(),
+                }))
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    /// Index of the address slot (`0` or `1`) the master matched on the most
+    /// recent addressing, read from the `MATCH` register.
+    fn matched_address(&self) -> u8 {
+        self.0.match_.read().bits() as u8
+    }
+
+    /// Wait for a master write, receiving into `buffer`, and return the number
+    /// of bytes that landed in it.
+    pub fn rx(&mut self, buffer: &mut [u8]) -> Result<usize, Error> {
+        if buffer.len() > EASY_DMA_SIZE {
+            return Err(Error::RxBufferTooLong);
+        }
+
+        self.set_rx_buffer(buffer)?;
+
+        compiler_fence(SeqCst);
+
+        // Resume reception and wait for the transaction to end.
+        self.0.tasks_preparerx.write(|w| unsafe { w.bits(1) });
+        self.0.tasks_resume.write(|w| unsafe { w.bits(1) });
+
+        while self.0.events_stopped.read().bits() == 0 {
+            if self.0.events_error.read().bits() != 0 {
+                self.0.events_error.write(|w| w);
+                return Err(Error::Receive);
+            }
+        }
+        self.0.events_stopped.write(|w| w);
+
+        compiler_fence(SeqCst);
+
+        Ok(self.0.rxd.amount.read().bits() as usize)
+    }
+
+    /// Respond to a master read by clocking out `buffer`.
+    pub fn tx(&mut self, buffer: &[u8]) -> Result<(), Error> {
+        slice_in_ram_or(buffer, Error::DMABufferNotInDataMemory)?;
+
+        if buffer.len() > EASY_DMA_SIZE {
+            return Err(Error::TxBufferTooLong);
+        }
+
+        self.set_tx_buffer(buffer)?;
+
+        compiler_fence(SeqCst);
+
+        self.0.tasks_preparetx.write(|w| unsafe { w.bits(1) });
+        self.0.tasks_resume.write(|w| unsafe { w.bits(1) });
+
+        while self.0.events_stopped.read().bits() == 0 {
+            if self.0.events_error.read().bits() != 0 {
+                self.0.events_error.write(|w| w);
+                return Err(Error::Transmit);
+            }
+        }
+        self.0.events_stopped.write(|w| w);
+
+        compiler_fence(SeqCst);
+
+        Ok(())
+    }
+
+    /// Async variant of [`Twis::rx`].
+    pub async fn rx_async(&mut self, buffer: &mut [u8]) -> Result<usize, Error> {
+        if buffer.len() > EASY_DMA_SIZE {
+            return Err(Error::RxBufferTooLong);
+        }
+
+        self.set_rx_buffer(buffer)?;
+
+        compiler_fence(SeqCst);
+
+        self.0.tasks_preparerx.write(|w| unsafe { w.bits(1) });
+        self.0.tasks_resume.write(|w| unsafe { w.bits(1) });
+
+        self.wait_stopped().await.map_err(|()| Error::Receive)?;
+
+        compiler_fence(SeqCst);
+
+        Ok(self.0.rxd.amount.read().bits() as usize)
+    }
+
+    /// Async variant of [`Twis::tx`].
+    pub async fn tx_async(&mut self, buffer: &[u8]) -> Result<(), Error> {
+        slice_in_ram_or(buffer, Error::DMABufferNotInDataMemory)?;
+
+        if buffer.len() > EASY_DMA_SIZE {
+            return Err(Error::TxBufferTooLong);
+        }
+
+        self.set_tx_buffer(buffer)?;
+
+        compiler_fence(SeqCst);
+
+        self.0.tasks_preparetx.write(|w| unsafe { w.bits(1) });
+        self.0.tasks_resume.write(|w| unsafe { w.bits(1) });
+
+        self.wait_stopped().await.map_err(|()| Error::Transmit)?;
+
+        compiler_fence(SeqCst);
+
+        Ok(())
+    }
+
+    /// Register the RX EasyDMA buffer.
+    fn set_rx_buffer(&mut self, buffer: &mut [u8]) -> Result<(), Error> {
+        if buffer.len() > EASY_DMA_SIZE {
+            return Err(Error::RxBufferTooLong);
+        }
+        self.0
+            .rxd
+            .ptr
+            .write(|w| unsafe { w.ptr().bits(buffer.as_mut_ptr() as u32) });
+        self.0
+            .rxd
+            .maxcnt
+            .write(|w| unsafe { w.maxcnt().bits(buffer.len() as _) });
+        Ok(())
+    }
+
+    /// Register the TX EasyDMA buffer.
+    fn set_tx_buffer(&mut self, buffer: &[u8]) -> Result<(), Error> {
+        if buffer.len() > EASY_DMA_SIZE {
+            return Err(Error::TxBufferTooLong);
+        }
+        self.0
+            .txd
+            .ptr
+            .write(|w| unsafe { w.ptr().bits(buffer.as_ptr() as u32) });
+        self.0
+            .txd
+            .maxcnt
+            .write(|w| unsafe { w.maxcnt().bits(buffer.len() as _) });
+        Ok(())
+    }
+
+    /// Enable the STOPPED/ERROR interrupts and park the task until the
+    /// handler reports the transaction is finished.
+    ///
+    /// Returns `Err(())` if the ERROR event fired; the caller maps it to the
+    /// direction-appropriate [`Error`] so a TX failure isn't reported as a
+    /// receive error.
+    async fn wait_stopped(&mut self) -> Result<(), ()> {
+        self.0.intenset.write(|w| {
+            w.stopped().set_bit();
+            w.error().set_bit()
+        });
+
+        poll_fn(|cx| {
+            T::state().waker.register(cx.waker());
+
+            if self.0.events_error.read().bits() != 0 {
+                self.0.events_error.write(|w| w);
+                Poll::Ready(Err(()))
+            } else if self.0.events_stopped.read().bits() != 0 {
+                Poll::Ready(Ok(()))
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    /// Interrupt handler for this TWIS instance.
+    ///
+    /// Bind it to the instance's interrupt vector. It disables every TWIS
+    /// interrupt source so the line cannot re-fire, then wakes the task
+    /// awaiting [`Twis::rx_async`]/[`Twis::tx_async`].
+    pub fn on_interrupt() {
+        let twis = unsafe { &*T::REGISTERS };
+        twis.intenclr.write(|w| unsafe { w.bits(!0) });
+        T::state().waker.wake();
+    }
+
+    /// Return the raw interface to the underlying TWIS peripheral.
+    pub fn free(self) -> T {
+        self.0
+    }
+}
+
+/// The direction of a transfer requested by the I2C master.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command {
+    /// The master wants to read from us; supply a TX buffer with [`Twis::tx`].
+    /// `address` is the index (`0` or `1`) of the matched address slot.
+    Read { address: u8 },
+    /// The master wants to write to us; the data lands in the RX buffer.
+    /// `address` is the index (`0` or `1`) of the matched address slot.
+    Write { address: u8 },
+}
+
+#[derive(Debug)]
+pub enum Error {
+    TxBufferTooLong,
+    RxBufferTooLong,
+    Transmit,
+    Receive,
+    DMABufferNotInDataMemory,
+}
+
+/// Per-instance driver state shared with the interrupt handler.
+pub struct State {
+    waker: AtomicWaker,
+}
+
+impl State {
+    const fn new() -> Self {
+        State {
+            waker: AtomicWaker::new(),
+        }
+    }
+}
+
+/// Implemented by all TWIS instances
+pub trait Instance: Deref<Target = twis0::RegisterBlock> {
+    /// Raw pointer to this instance's register block, for use from the
+    /// interrupt handler where no `&self` is available.
+    const REGISTERS: *const twis0::RegisterBlock;
+
+    /// Interrupt line shared by this instance.
+    const INTERRUPT: Interrupt;
+
+    /// Driver state shared between the futures and the interrupt handler.
+    fn state() -> &'static State;
+}
+
+static TWIS0_STATE: State = State::new();
+
+impl Instance for TWIS0 {
+    const REGISTERS: *const twis0::RegisterBlock = TWIS0::ptr();
+    const INTERRUPT: Interrupt = Interrupt::SPIM0_SPIS0_TWIM0_TWIS0_SPI0_TWI0;
+
+    fn state() -> &'static State {
+        &TWIS0_STATE
+    }
+}
+
+#[cfg(any(feature = "52832", feature = "52840"))]
+static TWIS1_STATE: State = State::new();
+
+#[cfg(any(feature = "52832", feature = "52840"))]
+impl Instance for TWIS1 {
+    const REGISTERS: *const twis0::RegisterBlock = TWIS1::ptr();
+    const INTERRUPT: Interrupt = Interrupt::SPIM1_SPIS1_TWIM1_TWIS1_SPI1_TWI1;
+
+    fn state() -> &'static State {
+        &TWIS1_STATE
+    }
+}